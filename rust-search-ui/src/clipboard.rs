@@ -1,15 +1,66 @@
 //! Cross-platform clipboard abstraction with Termux support.
 //!
-//! On Android/Termux (or with the `termux` feature), uses
-//! `termux-clipboard-set` and `termux-clipboard-get` commands.
-//! On other platforms, uses the `arboard` crate.
+//! Clipboard access is modeled as a small [`ClipboardBackend`] trait so the
+//! concrete mechanism can be chosen at runtime rather than baked in at
+//! compile time. [`backend()`] probes, in order: a Termux clipboard
+//! binary, the `arboard` crate, an external command-line tool
+//! (`xsel`/`xclip`/`wl-copy`), and finally a silent no-op. This lets a
+//! single binary degrade gracefully on headless servers, Wayland-only
+//! sessions, or machines with no clipboard tooling installed, instead of
+//! hard-failing based on the platform it was compiled for.
+//!
+//! Image clipboard access (`set_image`/`get_image`/`set_png`/`get_png`) is
+//! only supported on the `arboard` backend; other backends report an error
+//! rather than misbehaving silently.
 
 use anyhow::Result;
+use std::sync::OnceLock;
 
-/// Set text to the clipboard.
-pub fn set_text(text: &str) -> Result<()> {
-    #[cfg(any(feature = "termux", target_os = "android"))]
-    {
+/// Which X11 selection to target. `CLIPBOARD` is the familiar Ctrl-C/Ctrl-V
+/// clipboard; `PRIMARY` holds the most recent mouse selection and is pasted
+/// with a middle-click.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+    Clipboard,
+    Primary,
+}
+
+/// A clipboard mechanism capable of reading and writing text.
+trait ClipboardBackend: Send + Sync {
+    fn set_text(&self, text: &str) -> Result<()>;
+    fn get_text(&self) -> Result<String>;
+
+    /// Set text to a specific `Selection`. Backends with no concept of
+    /// PRIMARY (Termux, the no-op backend, and arboard on non-Linux
+    /// platforms) transparently fall back to the normal clipboard.
+    fn set_text_with(&self, _selection: Selection, text: &str) -> Result<()> {
+        self.set_text(text)
+    }
+
+    /// Get text from a specific `Selection`; see [`ClipboardBackend::set_text_with`].
+    fn get_text_with(&self, _selection: Selection) -> Result<String> {
+        self.get_text()
+    }
+
+    /// Set a raw RGBA image to the clipboard. Backends that can't represent
+    /// images (Termux, external CLI tools, the no-op backend) return an
+    /// error rather than silently dropping the image.
+    fn set_image(&self, _width: usize, _height: usize, _rgba: &[u8]) -> Result<()> {
+        anyhow::bail!("image clipboard not supported on this backend")
+    }
+
+    /// Get a raw RGBA image from the clipboard as `(width, height, rgba)`.
+    fn get_image(&self) -> Result<(usize, usize, Vec<u8>)> {
+        anyhow::bail!("image clipboard not supported on this backend")
+    }
+}
+
+/// Shells out to `termux-clipboard-set`/`termux-clipboard-get`, as provided
+/// by Termux:API on Android.
+struct TermuxBackend;
+
+impl ClipboardBackend for TermuxBackend {
+    fn set_text(&self, text: &str) -> Result<()> {
         use std::io::Write;
         use std::process::{Command, Stdio};
 
@@ -29,33 +80,696 @@ pub fn set_text(text: &str) -> Result<()> {
         }
     }
 
-    #[cfg(all(not(feature = "termux"), not(target_os = "android")))]
+    fn get_text(&self) -> Result<String> {
+        use std::process::Command;
+
+        let output = Command::new("termux-clipboard-get").output()?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        } else {
+            anyhow::bail!("termux-clipboard-get failed")
+        }
+    }
+}
+
+/// Uses the `arboard` crate, which covers the OS-native clipboard on
+/// Windows, macOS, and X11/Wayland Linux. On Linux, `Selection::Primary`
+/// is honored via arboard's `SetExtLinux`/`GetExtLinux` extension traits;
+/// on Windows and macOS, which have no PRIMARY selection, it transparently
+/// falls back to the normal clipboard.
+struct ArboardBackend;
+
+/// Set `text` to `selection`, using arboard's Linux-only selection
+/// extension where available. Returns the raw `arboard::Error` so callers
+/// can distinguish "the backend is unusable" from ordinary error kinds.
+fn arboard_set_text(selection: Selection, text: &str) -> std::result::Result<(), arboard::Error> {
+    #[cfg(target_os = "linux")]
+    {
+        use arboard::{LinuxClipboardKind, SetExtLinux};
+
+        let kind = match selection {
+            Selection::Clipboard => LinuxClipboardKind::Clipboard,
+            Selection::Primary => LinuxClipboardKind::Primary,
+        };
+
+        let mut clipboard = arboard::Clipboard::new()?;
+        clipboard.set().clipboard(kind).text(text.to_string())
+    }
+
+    #[cfg(not(target_os = "linux"))]
     {
+        let _ = selection;
         let mut clipboard = arboard::Clipboard::new()?;
-        clipboard.set_text(text)?;
-        Ok(())
+        clipboard.set_text(text.to_string())
     }
 }
 
-/// Get text from the clipboard.
-#[allow(dead_code)]
-pub fn get_text() -> Result<String> {
-    #[cfg(any(feature = "termux", target_os = "android"))]
+/// Get text from `selection`, using arboard's Linux-only selection
+/// extension where available. Returns the raw `arboard::Error` so callers
+/// can distinguish `ContentNotAvailable` (clipboard is simply empty) from
+/// errors that indicate the backend itself is unusable.
+fn arboard_get_text(selection: Selection) -> std::result::Result<String, arboard::Error> {
+    #[cfg(target_os = "linux")]
     {
-        use std::process::Command;
+        use arboard::{GetExtLinux, LinuxClipboardKind};
 
-        let output = Command::new("termux-clipboard-get").output()?;
+        let kind = match selection {
+            Selection::Clipboard => LinuxClipboardKind::Clipboard,
+            Selection::Primary => LinuxClipboardKind::Primary,
+        };
+
+        let mut clipboard = arboard::Clipboard::new()?;
+        clipboard.get().clipboard(kind).text()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = selection;
+        let mut clipboard = arboard::Clipboard::new()?;
+        clipboard.get_text()
+    }
+}
+
+impl ClipboardBackend for ArboardBackend {
+    fn set_text(&self, text: &str) -> Result<()> {
+        self.set_text_with(Selection::Clipboard, text)
+    }
+
+    fn get_text(&self) -> Result<String> {
+        self.get_text_with(Selection::Clipboard)
+    }
+
+    // arboard can construct successfully but still fail on individual
+    // operations (e.g. a Wayland session without the data-control
+    // protocol), so a failed operation here falls back to an external
+    // command-line tool, just like a failed construction does.
+    fn set_text_with(&self, selection: Selection, text: &str) -> Result<()> {
+        match arboard_set_text(selection, text) {
+            Ok(()) => Ok(()),
+            Err(err) => match fallback_command_backend() {
+                Some(fallback) => fallback.set_text_with(selection, text),
+                None => Err(err.into()),
+            },
+        }
+    }
+
+    // `ContentNotAvailable` means the clipboard is simply empty, which is an
+    // ordinary state rather than evidence the arboard backend is broken, so
+    // it's returned as-is instead of triggering a fallback subprocess spawn.
+    fn get_text_with(&self, selection: Selection) -> Result<String> {
+        match arboard_get_text(selection) {
+            Ok(text) => Ok(text),
+            Err(err @ arboard::Error::ContentNotAvailable) => Err(err.into()),
+            Err(err) => match fallback_command_backend() {
+                Some(fallback) => fallback.get_text_with(selection),
+                None => Err(err.into()),
+            },
+        }
+    }
+
+    fn set_image(&self, width: usize, height: usize, rgba: &[u8]) -> Result<()> {
+        let mut clipboard = arboard::Clipboard::new()?;
+        clipboard.set_image(arboard::ImageData {
+            width,
+            height,
+            bytes: rgba.into(),
+        })?;
+        Ok(())
+    }
+
+    fn get_image(&self) -> Result<(usize, usize, Vec<u8>)> {
+        let mut clipboard = arboard::Clipboard::new()?;
+        let image = clipboard.get_image()?;
+        Ok((image.width, image.height, image.bytes.into_owned()))
+    }
+}
+
+/// Which external clipboard tool a `CommandBackend` wraps.
+#[derive(Clone, Copy)]
+enum CommandTool {
+    Xsel,
+    Xclip,
+    WlClipboard,
+}
+
+/// Shells out to an external clipboard tool discovered on `PATH`
+/// (`xsel`, `xclip`, or `wl-copy`/`wl-paste`).
+struct CommandBackend {
+    tool: CommandTool,
+}
+
+impl CommandBackend {
+    fn copy_invocation(&self, selection: Selection) -> (&'static str, Vec<&'static str>) {
+        match (self.tool, selection) {
+            (CommandTool::Xsel, Selection::Clipboard) => ("xsel", vec!["--input", "--clipboard"]),
+            (CommandTool::Xsel, Selection::Primary) => ("xsel", vec!["--input", "--primary"]),
+            (CommandTool::Xclip, Selection::Clipboard) => {
+                ("xclip", vec!["-in", "-selection", "clipboard"])
+            }
+            (CommandTool::Xclip, Selection::Primary) => {
+                ("xclip", vec!["-in", "-selection", "primary"])
+            }
+            (CommandTool::WlClipboard, Selection::Clipboard) => ("wl-copy", vec![]),
+            (CommandTool::WlClipboard, Selection::Primary) => ("wl-copy", vec!["--primary"]),
+        }
+    }
+
+    fn paste_invocation(&self, selection: Selection) -> (&'static str, Vec<&'static str>) {
+        match (self.tool, selection) {
+            (CommandTool::Xsel, Selection::Clipboard) => ("xsel", vec!["--output", "--clipboard"]),
+            (CommandTool::Xsel, Selection::Primary) => ("xsel", vec!["--output", "--primary"]),
+            (CommandTool::Xclip, Selection::Clipboard) => {
+                ("xclip", vec!["-out", "-selection", "clipboard"])
+            }
+            (CommandTool::Xclip, Selection::Primary) => {
+                ("xclip", vec!["-out", "-selection", "primary"])
+            }
+            (CommandTool::WlClipboard, Selection::Clipboard) => ("wl-paste", vec![]),
+            (CommandTool::WlClipboard, Selection::Primary) => ("wl-paste", vec!["--primary"]),
+        }
+    }
+}
+
+impl ClipboardBackend for CommandBackend {
+    fn set_text(&self, text: &str) -> Result<()> {
+        self.set_text_with(Selection::Clipboard, text)
+    }
+
+    fn get_text(&self) -> Result<String> {
+        self.get_text_with(Selection::Clipboard)
+    }
+
+    fn set_text_with(&self, selection: Selection, text: &str) -> Result<()> {
+        use std::io::Write;
+        use std::process::{Command, Stdio};
+
+        let (program, args) = self.copy_invocation(selection);
+        let mut child = Command::new(program)
+            .args(&args)
+            .stdin(Stdio::piped())
+            .spawn()?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes())?;
+        }
 
+        let status = child.wait()?;
+        if status.success() {
+            Ok(())
+        } else {
+            anyhow::bail!("{program} failed")
+        }
+    }
+
+    fn get_text_with(&self, selection: Selection) -> Result<String> {
+        use std::process::Command;
+
+        let (program, args) = self.paste_invocation(selection);
+        let output = Command::new(program).args(&args).output()?;
         if output.status.success() {
             Ok(String::from_utf8_lossy(&output.stdout).to_string())
         } else {
-            anyhow::bail!("termux-clipboard-get failed")
+            anyhow::bail!("{program} failed")
         }
     }
+}
 
-    #[cfg(all(not(feature = "termux"), not(target_os = "android")))]
-    {
-        let mut clipboard = arboard::Clipboard::new()?;
-        Ok(clipboard.get_text()?)
+/// Silently succeeds without touching any real clipboard. Used as a last
+/// resort so callers on unusual platforms degrade gracefully instead of
+/// erroring on every clipboard access.
+struct NopBackend;
+
+impl ClipboardBackend for NopBackend {
+    fn set_text(&self, _text: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_text(&self) -> Result<String> {
+        Ok(String::new())
+    }
+}
+
+/// Check whether `program` can be found as an executable on `$PATH`.
+fn is_program_in_path(program: &str) -> bool {
+    let Ok(path) = std::env::var("PATH") else {
+        return false;
+    };
+
+    path.split(':').any(|dir| {
+        std::fs::metadata(std::path::Path::new(dir).join(program))
+            .map(|meta| meta.is_file() && is_executable(&meta))
+            .unwrap_or(false)
+    })
+}
+
+#[cfg(unix)]
+fn is_executable(meta: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    meta.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_meta: &std::fs::Metadata) -> bool {
+    true
+}
+
+/// Probe for the first `CommandBackend` tool available on `PATH`, preferring
+/// `xsel`, then `xclip`, then `wl-copy`/`wl-paste`.
+fn command_backend() -> Option<CommandBackend> {
+    if is_program_in_path("xsel") {
+        Some(CommandBackend {
+            tool: CommandTool::Xsel,
+        })
+    } else if is_program_in_path("xclip") {
+        Some(CommandBackend {
+            tool: CommandTool::Xclip,
+        })
+    } else if is_program_in_path("wl-copy") && is_program_in_path("wl-paste") {
+        Some(CommandBackend {
+            tool: CommandTool::WlClipboard,
+        })
+    } else {
+        None
+    }
+}
+
+/// The external command-line fallback tool, probed and cached once. Used
+/// both to pick the top-level backend when arboard isn't available at all,
+/// and by `ArboardBackend` to fall back when arboard is available but a
+/// given operation fails.
+fn fallback_command_backend() -> Option<&'static CommandBackend> {
+    static FALLBACK: OnceLock<Option<CommandBackend>> = OnceLock::new();
+    FALLBACK.get_or_init(command_backend).as_ref()
+}
+
+/// Probe the system once for the best available backend, in order: Termux,
+/// arboard, an external command-line tool, then a silent no-op.
+fn probe_backend() -> Box<dyn ClipboardBackend> {
+    if is_program_in_path("termux-clipboard-set") {
+        return Box::new(TermuxBackend);
+    }
+
+    if arboard::Clipboard::new().is_ok() {
+        return Box::new(ArboardBackend);
+    }
+
+    if let Some(tool) = fallback_command_backend() {
+        return Box::new(CommandBackend { tool: tool.tool });
+    }
+
+    Box::new(NopBackend)
+}
+
+/// The process-wide clipboard backend, probed and cached on first use.
+fn backend() -> &'static dyn ClipboardBackend {
+    static BACKEND: OnceLock<Box<dyn ClipboardBackend>> = OnceLock::new();
+    BACKEND.get_or_init(probe_backend).as_ref()
+}
+
+/// Split a shell command string into a program and its arguments on
+/// whitespace. This is intentionally simple (no quoting support); documented
+/// as such for `CCTOOLS_CLIPBOARD_COPY_CMD`/`CCTOOLS_CLIPBOARD_PASTE_CMD`.
+fn split_command(cmd: &str) -> Option<(&str, Vec<&str>)> {
+    let mut parts = cmd.split_whitespace();
+    let program = parts.next()?;
+    Some((program, parts.collect()))
+}
+
+/// Pipe `text` into the stdin of the user-configured copy command.
+fn set_text_via_command(cmd: &str, text: &str) -> Result<()> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let (program, args) =
+        split_command(cmd).ok_or_else(|| anyhow::anyhow!("CCTOOLS_CLIPBOARD_COPY_CMD is empty"))?;
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        anyhow::bail!("clipboard copy command `{cmd}` exited with {status}")
+    }
+}
+
+/// Read the stdout of the user-configured paste command.
+fn get_text_via_command(cmd: &str) -> Result<String> {
+    use std::process::Command;
+
+    let (program, args) = split_command(cmd)
+        .ok_or_else(|| anyhow::anyhow!("CCTOOLS_CLIPBOARD_PASTE_CMD is empty"))?;
+
+    let output = Command::new(program).args(args).output()?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        anyhow::bail!(
+            "clipboard paste command `{cmd}` exited with {}",
+            output.status
+        )
+    }
+}
+
+/// Set text to the `CLIPBOARD` selection.
+///
+/// If `CCTOOLS_CLIPBOARD_COPY_CMD` is set, it takes priority over the
+/// auto-detected backend, letting users route the clipboard through
+/// SSH-forwarding helpers, `pbcopy` wrappers, tmux buffers, or
+/// remote-desktop bridges. The command string is split on whitespace into a
+/// program and its arguments; shell quoting is not supported.
+pub fn set_text(text: &str) -> Result<()> {
+    set_text_with(Selection::Clipboard, text)
+}
+
+/// Get text from the `CLIPBOARD` selection.
+///
+/// If `CCTOOLS_CLIPBOARD_PASTE_CMD` is set, it takes priority over the
+/// auto-detected backend; see [`set_text`].
+#[allow(dead_code)]
+pub fn get_text() -> Result<String> {
+    get_text_with(Selection::Clipboard)
+}
+
+/// Set text to the given X11 `Selection`. `Selection::Primary` is honored
+/// on the command-line backend and, on Linux, the arboard backend; Termux,
+/// arboard on non-Linux platforms, and the no-op backend transparently fall
+/// back to the normal clipboard.
+///
+/// `CCTOOLS_CLIPBOARD_COPY_CMD` only intercepts `Selection::Clipboard`; a
+/// `Selection::Primary` request always goes to the auto-detected backend,
+/// even when the env var is set.
+#[allow(dead_code)]
+pub fn set_text_with(selection: Selection, text: &str) -> Result<()> {
+    if let Some(cmd) = copy_cmd_override(selection) {
+        return set_text_via_command(&cmd, text);
+    }
+
+    backend().set_text_with(selection, text)
+}
+
+/// Get text from the given X11 `Selection`; see [`set_text_with`].
+///
+/// `CCTOOLS_CLIPBOARD_PASTE_CMD` only intercepts `Selection::Clipboard`; a
+/// `Selection::Primary` request always goes to the auto-detected backend,
+/// even when the env var is set.
+#[allow(dead_code)]
+pub fn get_text_with(selection: Selection) -> Result<String> {
+    if let Some(cmd) = paste_cmd_override(selection) {
+        return get_text_via_command(&cmd);
+    }
+
+    backend().get_text_with(selection)
+}
+
+/// The `CCTOOLS_CLIPBOARD_COPY_CMD` override, if one applies to `selection`.
+/// Always `None` for `Selection::Primary`, regardless of whether the env var
+/// is set, so the routing decision doesn't depend on process environment.
+fn copy_cmd_override(selection: Selection) -> Option<String> {
+    (selection == Selection::Clipboard)
+        .then(|| std::env::var("CCTOOLS_CLIPBOARD_COPY_CMD").ok())
+        .flatten()
+}
+
+/// The `CCTOOLS_CLIPBOARD_PASTE_CMD` override, if one applies to `selection`;
+/// see [`copy_cmd_override`].
+fn paste_cmd_override(selection: Selection) -> Option<String> {
+    (selection == Selection::Clipboard)
+        .then(|| std::env::var("CCTOOLS_CLIPBOARD_PASTE_CMD").ok())
+        .flatten()
+}
+
+/// Set a raw RGBA image to the clipboard.
+#[allow(dead_code)]
+pub fn set_image(width: usize, height: usize, rgba: &[u8]) -> Result<()> {
+    backend().set_image(width, height, rgba)
+}
+
+/// Get a raw RGBA image from the clipboard as `(width, height, rgba)`.
+#[allow(dead_code)]
+pub fn get_image() -> Result<(usize, usize, Vec<u8>)> {
+    backend().get_image()
+}
+
+/// Decode a PNG and set it as the clipboard image.
+#[allow(dead_code)]
+pub fn set_png(png: &[u8]) -> Result<()> {
+    let image = image::load_from_memory(png)?.to_rgba8();
+    let (width, height) = image.dimensions();
+    set_image(width as usize, height as usize, image.as_raw())
+}
+
+/// Get the clipboard image and encode it as a PNG.
+#[allow(dead_code)]
+pub fn get_png() -> Result<Vec<u8>> {
+    let (width, height, rgba) = get_image()?;
+    let image = image::RgbaImage::from_raw(width as u32, height as u32, rgba)
+        .ok_or_else(|| anyhow::anyhow!("clipboard image had an invalid buffer size"))?;
+
+    let mut png = Vec::new();
+    image.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)?;
+    Ok(png)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command_backend_with(tool: CommandTool) -> CommandBackend {
+        CommandBackend { tool }
+    }
+
+    #[test]
+    fn xsel_copy_invocation() {
+        assert_eq!(
+            command_backend_with(CommandTool::Xsel).copy_invocation(Selection::Clipboard),
+            ("xsel", vec!["--input", "--clipboard"])
+        );
+        assert_eq!(
+            command_backend_with(CommandTool::Xsel).copy_invocation(Selection::Primary),
+            ("xsel", vec!["--input", "--primary"])
+        );
+    }
+
+    #[test]
+    fn xsel_paste_invocation() {
+        assert_eq!(
+            command_backend_with(CommandTool::Xsel).paste_invocation(Selection::Clipboard),
+            ("xsel", vec!["--output", "--clipboard"])
+        );
+        assert_eq!(
+            command_backend_with(CommandTool::Xsel).paste_invocation(Selection::Primary),
+            ("xsel", vec!["--output", "--primary"])
+        );
+    }
+
+    #[test]
+    fn xclip_copy_invocation() {
+        assert_eq!(
+            command_backend_with(CommandTool::Xclip).copy_invocation(Selection::Clipboard),
+            ("xclip", vec!["-in", "-selection", "clipboard"])
+        );
+        assert_eq!(
+            command_backend_with(CommandTool::Xclip).copy_invocation(Selection::Primary),
+            ("xclip", vec!["-in", "-selection", "primary"])
+        );
+    }
+
+    #[test]
+    fn xclip_paste_invocation() {
+        assert_eq!(
+            command_backend_with(CommandTool::Xclip).paste_invocation(Selection::Clipboard),
+            ("xclip", vec!["-out", "-selection", "clipboard"])
+        );
+        assert_eq!(
+            command_backend_with(CommandTool::Xclip).paste_invocation(Selection::Primary),
+            ("xclip", vec!["-out", "-selection", "primary"])
+        );
+    }
+
+    #[test]
+    fn wl_clipboard_copy_invocation() {
+        assert_eq!(
+            command_backend_with(CommandTool::WlClipboard).copy_invocation(Selection::Clipboard),
+            ("wl-copy", vec![])
+        );
+        assert_eq!(
+            command_backend_with(CommandTool::WlClipboard).copy_invocation(Selection::Primary),
+            ("wl-copy", vec!["--primary"])
+        );
+    }
+
+    #[test]
+    fn wl_clipboard_paste_invocation() {
+        assert_eq!(
+            command_backend_with(CommandTool::WlClipboard).paste_invocation(Selection::Clipboard),
+            ("wl-paste", vec![])
+        );
+        assert_eq!(
+            command_backend_with(CommandTool::WlClipboard).paste_invocation(Selection::Primary),
+            ("wl-paste", vec!["--primary"])
+        );
+    }
+
+    #[test]
+    fn split_command_splits_program_and_args() {
+        assert_eq!(
+            split_command("xclip -in -selection clipboard"),
+            Some(("xclip", vec!["-in", "-selection", "clipboard"]))
+        );
+    }
+
+    #[test]
+    fn split_command_program_only() {
+        assert_eq!(split_command("pbcopy"), Some(("pbcopy", vec![])));
+    }
+
+    #[test]
+    fn split_command_empty_is_none() {
+        assert_eq!(split_command(""), None);
+    }
+
+    #[test]
+    fn split_command_whitespace_only_is_none() {
+        assert_eq!(split_command("   "), None);
+    }
+
+    #[test]
+    fn is_executable_true_for_executable_file() {
+        let meta =
+            std::fs::metadata("/bin/sh").expect("/bin/sh should exist in the test environment");
+        assert!(is_executable(&meta));
+    }
+
+    #[test]
+    fn is_executable_false_for_non_executable_file() {
+        let path = std::env::temp_dir().join(format!(
+            "cctools-clipboard-test-not-executable-{}",
+            std::process::id()
+        ));
+        std::fs::write(&path, b"not executable").unwrap();
+
+        let meta = std::fs::metadata(&path).unwrap();
+        let result = is_executable(&meta);
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(!result);
+    }
+
+    #[test]
+    fn is_program_in_path_finds_sh() {
+        assert!(is_program_in_path("sh"));
+    }
+
+    #[test]
+    fn is_program_in_path_rejects_unknown_program() {
+        assert!(!is_program_in_path(
+            "cctools-clipboard-definitely-not-a-real-binary"
+        ));
+    }
+
+    #[test]
+    fn nop_backend_set_image_is_unsupported() {
+        assert!(NopBackend.set_image(1, 1, &[0, 0, 0, 255]).is_err());
+    }
+
+    #[test]
+    fn nop_backend_get_image_is_unsupported() {
+        assert!(NopBackend.get_image().is_err());
+    }
+
+    #[test]
+    fn command_backend_set_image_is_unsupported() {
+        let backend = command_backend_with(CommandTool::Xsel);
+        assert!(backend.set_image(1, 1, &[0, 0, 0, 255]).is_err());
+    }
+
+    #[test]
+    fn command_backend_get_image_is_unsupported() {
+        let backend = command_backend_with(CommandTool::Xsel);
+        assert!(backend.get_image().is_err());
+    }
+
+    #[test]
+    fn png_round_trip_preserves_rgba_buffer() {
+        let (width, height) = (2u32, 2u32);
+        let rgba: Vec<u8> = vec![
+            255, 0, 0, 255, //
+            0, 255, 0, 255, //
+            0, 0, 255, 255, //
+            255, 255, 255, 255, //
+        ];
+
+        let image = image::RgbaImage::from_raw(width, height, rgba.clone()).unwrap();
+        let mut png = Vec::new();
+        image
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+
+        let decoded = image::load_from_memory(&png).unwrap().to_rgba8();
+        assert_eq!(decoded.dimensions(), (width, height));
+        assert_eq!(decoded.as_raw(), &rgba);
+    }
+
+    #[test]
+    fn primary_selection_bypasses_copy_cmd_override() {
+        let key = "CCTOOLS_CLIPBOARD_COPY_CMD";
+        let previous = std::env::var(key).ok();
+        std::env::set_var(key, "cctools-clipboard-test-cmd-does-not-exist");
+
+        let primary = copy_cmd_override(Selection::Primary);
+        let clipboard = copy_cmd_override(Selection::Clipboard);
+
+        match previous {
+            Some(value) => std::env::set_var(key, value),
+            None => std::env::remove_var(key),
+        }
+
+        assert_eq!(
+            primary, None,
+            "Selection::Primary should never be routed through CCTOOLS_CLIPBOARD_COPY_CMD"
+        );
+        assert_eq!(
+            clipboard.as_deref(),
+            Some("cctools-clipboard-test-cmd-does-not-exist")
+        );
+    }
+
+    #[test]
+    fn primary_selection_bypasses_paste_cmd_override() {
+        let key = "CCTOOLS_CLIPBOARD_PASTE_CMD";
+        let previous = std::env::var(key).ok();
+        std::env::set_var(key, "cctools-clipboard-test-cmd-does-not-exist");
+
+        let primary = paste_cmd_override(Selection::Primary);
+        let clipboard = paste_cmd_override(Selection::Clipboard);
+
+        match previous {
+            Some(value) => std::env::set_var(key, value),
+            None => std::env::remove_var(key),
+        }
+
+        assert_eq!(
+            primary, None,
+            "Selection::Primary should never be routed through CCTOOLS_CLIPBOARD_PASTE_CMD"
+        );
+        assert_eq!(
+            clipboard.as_deref(),
+            Some("cctools-clipboard-test-cmd-does-not-exist")
+        );
+    }
+
+    #[test]
+    fn rgba_from_raw_rejects_mismatched_buffer_size() {
+        // Mirrors the size check in `get_png`: an RGBA buffer that doesn't
+        // match width*height*4 can't be turned into an image.
+        assert!(image::RgbaImage::from_raw(2, 2, vec![0u8; 3]).is_none());
     }
 }